@@ -0,0 +1,204 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transparent Body Compression
+//!
+//! Full and catch-up `sync`/`initial_sync` responses can carry thousands of UTXOs and be large
+//! on the wire. [`Codec`] negotiates compression for [`HttpTransport`](super::transport::HttpTransport)
+//! bodies above a size threshold: request bodies are compressed before sending and tagged with
+//! `Content-Encoding`, and response bodies are transparently decompressed according to their own
+//! `Content-Encoding` before [`Client`](super::http::Client) deserializes them. This is invisible
+//! to `signer::Connection` callers; only embedded/WASM builds that drop the `compression` feature
+//! see the uncompressed wire format.
+
+use alloc::vec::Vec;
+
+/// Maximum Size, in Bytes, that [`Codec::decompress`] will Inflate a Body To
+///
+/// Bounds how large a compressed response body is allowed to expand into, matching the cap
+/// [`QuicTransport`](super::quic::QuicTransport) already applies to its own stream reads, so a
+/// malicious or compromised signer can't turn a small compressed response into an unbounded
+/// allocation (a decompression bomb).
+pub const MAX_DECOMPRESSED_SIZE: usize = 16 * 1024 * 1024;
+
+/// Negotiated Compression Codec
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Codec {
+    /// No Compression
+    Identity,
+
+    /// Gzip Compression
+    Gzip,
+
+    /// Brotli Compression
+    Brotli,
+}
+
+impl Codec {
+    /// Returns the value this codec should advertise in a `Content-Encoding`/`Accept-Encoding`
+    /// header, or `None` for [`Codec::Identity`].
+    #[inline]
+    pub const fn header_value(self) -> Option<&'static str> {
+        match self {
+            Self::Identity => None,
+            Self::Gzip => Some("gzip"),
+            Self::Brotli => Some("br"),
+        }
+    }
+
+    /// Parses a `Content-Encoding` header value back into a [`Codec`].
+    #[inline]
+    pub fn from_header_value(value: &str) -> Self {
+        match value {
+            "gzip" => Self::Gzip,
+            "br" => Self::Brotli,
+            _ => Self::Identity,
+        }
+    }
+
+    /// Compresses `body` with `self`, returning it unchanged for [`Codec::Identity`].
+    #[inline]
+    pub fn compress(self, body: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Identity => body.to_vec(),
+            Self::Gzip => {
+                use flate2::{write::GzEncoder, Compression};
+                use std::io::Write;
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder
+                    .write_all(body)
+                    .expect("Writing to an in-memory buffer cannot fail.");
+                encoder
+                    .finish()
+                    .expect("Finishing an in-memory gzip stream cannot fail.")
+            }
+            Self::Brotli => {
+                use std::io::Write;
+                let mut out = Vec::new();
+                brotli::CompressorWriter::new(&mut out, 4096, 5, 22)
+                    .write_all(body)
+                    .expect("Writing to an in-memory buffer cannot fail.");
+                out
+            }
+        }
+    }
+
+    /// Decompresses `body` with `self`, returning it unchanged for [`Codec::Identity`].
+    ///
+    /// The inflated output is capped at [`MAX_DECOMPRESSED_SIZE`]; a body that would decompress
+    /// past that bound is rejected instead of being fully inflated into memory.
+    #[inline]
+    pub fn decompress(self, body: &[u8]) -> std::io::Result<Vec<u8>> {
+        use std::io::Read;
+
+        /// Reads at most `MAX_DECOMPRESSED_SIZE` bytes from `reader`, erroring if more remain.
+        fn read_bounded<R: Read>(mut reader: R) -> std::io::Result<Vec<u8>> {
+            let mut out = Vec::new();
+            reader
+                .by_ref()
+                .take(MAX_DECOMPRESSED_SIZE as u64 + 1)
+                .read_to_end(&mut out)?;
+            if out.len() > MAX_DECOMPRESSED_SIZE {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidData,
+                    "decompressed response body exceeds the maximum allowed size",
+                ));
+            }
+            Ok(out)
+        }
+
+        match self {
+            Self::Identity => Ok(body.to_vec()),
+            Self::Gzip => {
+                use flate2::read::GzDecoder;
+                read_bounded(GzDecoder::new(body))
+            }
+            Self::Brotli => read_bounded(brotli::Decompressor::new(body, 4096)),
+        }
+    }
+}
+
+/// Compression Configuration
+///
+/// Controls which [`Codec`] an [`HttpTransport`](super::transport::HttpTransport) compresses its
+/// outgoing request bodies with and the minimum body size, in bytes, before it bothers
+/// compressing at all (compressing a small request only adds CPU time for no bandwidth benefit).
+/// The `Accept-Encoding` header always advertises both gzip and brotli, whatever codec is set.
+#[derive(Clone, Copy, Debug)]
+pub struct CompressionConfig {
+    /// Codec to Compress Outgoing Bodies With
+    pub codec: Codec,
+
+    /// Minimum Body Size, in Bytes, Before Compression is Applied
+    pub threshold: usize,
+}
+
+impl CompressionConfig {
+    /// The default threshold below which compressing a body is not worth its CPU cost.
+    pub const DEFAULT_THRESHOLD: usize = 8 * 1024;
+}
+
+impl Default for CompressionConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            codec: Codec::Gzip,
+            threshold: Self::DEFAULT_THRESHOLD,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gzip_round_trips() {
+        let body = b"a sync response with plenty of repeated bytes ".repeat(64);
+        let compressed = Codec::Gzip.compress(&body);
+        assert_eq!(Codec::Gzip.decompress(&compressed).unwrap(), body);
+    }
+
+    #[test]
+    fn brotli_round_trips() {
+        let body = b"a sync response with plenty of repeated bytes ".repeat(64);
+        let compressed = Codec::Brotli.compress(&body);
+        assert_eq!(Codec::Brotli.decompress(&compressed).unwrap(), body);
+    }
+
+    #[test]
+    fn identity_round_trips_unchanged() {
+        let body = b"uncompressed".to_vec();
+        assert_eq!(Codec::Identity.compress(&body), body);
+        assert_eq!(Codec::Identity.decompress(&body).unwrap(), body);
+    }
+
+    #[test]
+    fn decompress_rejects_output_past_the_size_cap() {
+        let oversized = vec![0u8; MAX_DECOMPRESSED_SIZE + 1];
+        let compressed = Codec::Gzip.compress(&oversized);
+        assert!(Codec::Gzip.decompress(&compressed).is_err());
+    }
+
+    #[test]
+    fn from_header_value_round_trips_known_codecs() {
+        for codec in [Codec::Gzip, Codec::Brotli] {
+            let header = codec.header_value().unwrap();
+            assert_eq!(Codec::from_header_value(header), codec);
+        }
+        assert_eq!(Codec::from_header_value("identity"), Codec::Identity);
+    }
+}
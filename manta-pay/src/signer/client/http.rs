@@ -19,7 +19,15 @@
 use crate::{
     config::{utxo::Address, Config, Parameters},
     signer::{
-        client::network::{Message, Network},
+        client::{
+            network::{Message, Network},
+            storage::{MemoryStorage, Storage, SyncState},
+            transport::{HttpTransport, SendTransport, Transport},
+            warp::{
+                verify_base_checkpoint, WarpSyncError, WarpSyncRequest, WarpSyncResponse,
+                WarpSyncVerifier,
+            },
+        },
         AssetMetadata, Checkpoint, GetRequest, IdentityRequest, IdentityResponse,
         InitialSyncRequest, SignError, SignRequest, SignResponse, SignWithTransactionDataResult,
         SyncError, SyncRequest, SyncResponse, TransactionDataRequest, TransactionDataResponse,
@@ -29,47 +37,71 @@ use alloc::boxed::Box;
 use manta_accounting::wallet::{self, signer};
 use manta_util::{
     future::LocalBoxFutureResult,
-    http::reqwest::{self, IntoUrl, KnownUrlClient},
+    http::reqwest::IntoUrl,
     serde::{de::DeserializeOwned, Serialize},
 };
 
 #[doc(inline)]
-pub use reqwest::Error;
+pub use crate::signer::client::transport::TransportError as Error;
 
 /// Wallet Associated to [`Client`]
 pub type Wallet<L> = wallet::Wallet<Config, L, Client>;
 
 /// HTTP Signer Client
-pub struct Client {
-    /// Base Client
-    base: KnownUrlClient,
+pub struct Client<T = HttpTransport, S = MemoryStorage> {
+    /// Wire Transport
+    transport: T,
 
     /// Network Selector
     network: Option<Network>,
+
+    /// Sync Checkpoint Storage
+    storage: S,
 }
 
-impl Client {
-    /// Builds a new HTTP [`Client`] that connects to `server_url`.
+impl Client<HttpTransport, MemoryStorage> {
+    /// Builds a new HTTP [`Client`] that connects to `server_url`, keeping its sync checkpoint
+    /// in memory for the lifetime of the process.
+    ///
+    /// To resume sync across process restarts, or to run over a transport other than a plain
+    /// HTTP POST, use [`ClientBuilder`] instead.
     #[inline]
     pub fn new<U>(server_url: U) -> Result<Self, Error>
     where
         U: IntoUrl,
     {
-        Ok(Self {
-            base: KnownUrlClient::new(server_url)?,
-            network: None,
-        })
+        ClientBuilder::new(server_url)?.build()
     }
+}
 
-    /// Sets the network that will be used to wrap HTTP requests.
+impl<T, S> Client<T, S>
+where
+    T: Transport,
+    S: Storage,
+{
+    /// Sets the network that will be used to wrap outgoing requests.
     #[inline]
     pub fn set_network(&mut self, network: Option<Network>) {
         self.network = network
     }
 
+    /// Replaces the transport that `self` sends requests over, e.g. to switch from the default
+    /// [`HttpTransport`] to a persistent WebSocket connection.
+    #[inline]
+    pub fn set_transport<U>(self, transport: U) -> Client<U, S>
+    where
+        U: Transport,
+    {
+        Client {
+            transport,
+            network: self.network,
+            storage: self.storage,
+        }
+    }
+
     /// Wraps the current outgoing `request` with a `network` if it is not `None`.
     #[inline]
-    pub fn wrap_request<T>(&self, request: T) -> Message<T> {
+    pub fn wrap_request<R>(&self, request: R) -> Message<R> {
         Message {
             network: self
                 .network
@@ -78,18 +110,216 @@ impl Client {
         }
     }
 
-    /// Sends a POST of type `command` with query string `request`.
+    /// Sends a request of type `command` to the signer over [`Self::transport`](Transport),
+    /// returning the deserialized response.
+    #[inline]
+    pub async fn post_request<Req, R>(&self, command: &str, request: Req) -> Result<R, Error>
+    where
+        Req: Serialize,
+        R: DeserializeOwned,
+    {
+        let body = manta_util::serde_json::to_vec(&self.wrap_request(request))?;
+        let response = self.transport.send(command, body).await?;
+        Ok(manta_util::serde_json::from_slice(&response)?)
+    }
+
+    /// Loads the most recently persisted [`SyncState`] from storage, if any has been recorded.
+    #[inline]
+    pub async fn load_sync_state(&self) -> Result<Option<SyncState>, S::Error> {
+        self.storage.load().await
+    }
+
+    /// Sends a `command` sync request, first replacing `request`'s [`Checkpoint`] with the stored
+    /// one if `request` still carries the default (genesis) checkpoint (e.g. after a restart),
+    /// then persisting the response's [`Checkpoint`] so a future restart can resume from here
+    /// instead of re-downloading already-seen UTXOs. A request with any other checkpoint is sent
+    /// as is, without consulting storage.
+    pub(crate) async fn sync_request(
+        &mut self,
+        command: &str,
+        mut request: SyncRequest,
+    ) -> Result<Result<SyncResponse, SyncError>, Error> {
+        if request.checkpoint == Checkpoint::default() {
+            if let Ok(Some(state)) = self.load_sync_state().await {
+                request.checkpoint = state.checkpoint;
+            }
+        }
+        let response = self.post_request(command, request).await?;
+        if let Ok(ref response) = response {
+            let _ = self
+                .storage
+                .store(SyncState {
+                    checkpoint: response.checkpoint.clone(),
+                    response: response.clone(),
+                })
+                .await;
+        }
+        Ok(response)
+    }
+
+    /// Sends an `initial_sync` request, persisting the response's [`Checkpoint`] just like
+    /// [`Self::sync_request`] so that a wallet that restarts before its first regular
+    /// [`sync`](signer::Connection::sync) call resumes from here instead of redoing the initial
+    /// sync from genesis.
+    pub(crate) async fn initial_sync_request(
+        &mut self,
+        request: InitialSyncRequest,
+    ) -> Result<Result<SyncResponse, SyncError>, Error> {
+        let response = self.post_request("initial_sync", request).await?;
+        if let Ok(ref response) = response {
+            let _ = self
+                .storage
+                .store(SyncState {
+                    checkpoint: response.checkpoint.clone(),
+                    response: response.clone(),
+                })
+                .await;
+        }
+        Ok(response)
+    }
+
+    /// `Send`-future counterpart of [`Self::initial_sync_request`], for use by
+    /// [`SendClient`](super::send::SendClient) over a [`SendTransport`].
+    pub(crate) async fn initial_sync_request_send(
+        &mut self,
+        request: InitialSyncRequest,
+    ) -> Result<Result<SyncResponse, SyncError>, Error>
+    where
+        T: SendTransport,
+    {
+        let response = self.post_request_send("initial_sync", request).await?;
+        if let Ok(ref response) = response {
+            let _ = self
+                .storage
+                .store(SyncState {
+                    checkpoint: response.checkpoint.clone(),
+                    response: response.clone(),
+                })
+                .await;
+        }
+        Ok(response)
+    }
+
+    /// `Send`-future counterpart of [`Self::post_request`], for use by
+    /// [`SendClient`](super::send::SendClient) over a [`SendTransport`].
     #[inline]
-    pub async fn post_request<T, R>(&self, command: &str, request: T) -> reqwest::Result<R>
+    pub(crate) async fn post_request_send<Req, R>(
+        &self,
+        command: &str,
+        request: Req,
+    ) -> Result<R, Error>
     where
-        T: Serialize,
+        T: SendTransport,
+        Req: Serialize,
         R: DeserializeOwned,
     {
-        self.base.post(command, &self.wrap_request(request)).await
+        let body = manta_util::serde_json::to_vec(&self.wrap_request(request))?;
+        let response = self.transport.send_send(command, body).await?;
+        Ok(manta_util::serde_json::from_slice(&response)?)
+    }
+
+    /// `Send`-future counterpart of [`Self::sync_request`], for use by
+    /// [`SendClient`](super::send::SendClient) over a [`SendTransport`].
+    pub(crate) async fn sync_request_send(
+        &mut self,
+        command: &str,
+        mut request: SyncRequest,
+    ) -> Result<Result<SyncResponse, SyncError>, Error>
+    where
+        T: SendTransport,
+    {
+        if request.checkpoint == Checkpoint::default() {
+            if let Ok(Some(state)) = self.load_sync_state().await {
+                request.checkpoint = state.checkpoint;
+            }
+        }
+        let response = self.post_request_send(command, request).await?;
+        if let Ok(ref response) = response {
+            let _ = self
+                .storage
+                .store(SyncState {
+                    checkpoint: response.checkpoint.clone(),
+                    response: response.clone(),
+                })
+                .await;
+        }
+        Ok(response)
+    }
+
+    /// Performs a warp sync to `trusted`, skipping every intermediate delta that a normal
+    /// [`initial_sync`](signer::Connection::initial_sync) would have replayed from genesis.
+    ///
+    /// The signer is untrusted, so it may reject the request outright (e.g. because `trusted` is
+    /// too old and has since been pruned), surfaced as [`WarpSyncError::Sync`]; and even a
+    /// response it accepts is independently checked by `verifier` before being adopted, so a
+    /// server that tries to substitute a forged starting state or forged accumulator witnesses is
+    /// rejected with [`WarpSyncError::UntrustedCheckpoint`] instead of being trusted on its own
+    /// say-so. On success, the verified state is persisted so that future calls to
+    /// [`sync`](signer::Connection::sync) resume incrementally from here.
+    #[inline]
+    pub async fn warp_sync<V>(
+        &mut self,
+        trusted: Checkpoint,
+        verifier: V,
+    ) -> Result<SyncResponse, WarpSyncError>
+    where
+        V: WarpSyncVerifier,
+    {
+        let warp_response: Result<WarpSyncResponse, SyncError> = self
+            .post_request(
+                "warp_sync",
+                WarpSyncRequest {
+                    trusted_checkpoint: trusted.clone(),
+                },
+            )
+            .await?;
+        let response = verify_base_checkpoint(&trusted, warp_response?, verifier)?;
+        let _ = self
+            .storage
+            .store(SyncState {
+                checkpoint: response.checkpoint.clone(),
+                response: response.clone(),
+            })
+            .await;
+        Ok(response)
+    }
+
+    /// `Send`-future counterpart of [`Self::warp_sync`], for use by
+    /// [`SendClient`](super::send::SendClient) over a [`SendTransport`].
+    pub(crate) async fn warp_sync_send<V>(
+        &mut self,
+        trusted: Checkpoint,
+        verifier: V,
+    ) -> Result<SyncResponse, WarpSyncError>
+    where
+        T: SendTransport,
+        V: WarpSyncVerifier,
+    {
+        let warp_response: Result<WarpSyncResponse, SyncError> = self
+            .post_request_send(
+                "warp_sync",
+                WarpSyncRequest {
+                    trusted_checkpoint: trusted.clone(),
+                },
+            )
+            .await?;
+        let response = verify_base_checkpoint(&trusted, warp_response?, verifier)?;
+        let _ = self
+            .storage
+            .store(SyncState {
+                checkpoint: response.checkpoint.clone(),
+                response: response.clone(),
+            })
+            .await;
+        Ok(response)
     }
 }
 
-impl signer::Connection<Config> for Client {
+impl<T, S> signer::Connection<Config> for Client<T, S>
+where
+    T: Transport,
+    S: Storage,
+{
     type AssetMetadata = AssetMetadata;
     type Checkpoint = Checkpoint;
     type Error = Error;
@@ -99,7 +329,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: SyncRequest,
     ) -> LocalBoxFutureResult<Result<SyncResponse, SyncError>, Self::Error> {
-        Box::pin(self.post_request("sync", request))
+        Box::pin(self.sync_request("sync", request))
     }
 
     #[inline]
@@ -107,7 +337,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: SyncRequest,
     ) -> LocalBoxFutureResult<Result<SyncResponse, SyncError>, Self::Error> {
-        Box::pin(self.post_request("sbt_sync", request))
+        Box::pin(self.sync_request("sbt_sync", request))
     }
 
     #[inline]
@@ -115,7 +345,7 @@ impl signer::Connection<Config> for Client {
         &mut self,
         request: InitialSyncRequest,
     ) -> LocalBoxFutureResult<Result<SyncResponse, SyncError>, Self::Error> {
-        Box::pin(self.post_request("initial_sync", request))
+        Box::pin(self.initial_sync_request(request))
     }
 
     #[inline]
@@ -160,3 +390,86 @@ impl signer::Connection<Config> for Client {
         Box::pin(self.post_request("transfer_parameters", GetRequest::Get))
     }
 }
+
+/// HTTP Signer Client Builder
+///
+/// Builds a [`Client`] with a caller-chosen [`Transport`] and [`Storage`] backend, so that a
+/// wallet can opt into a persistent transport or durable sync checkpoints instead of the
+/// defaults. Use [`Client::new`] directly when neither is needed.
+pub struct ClientBuilder<T = HttpTransport, S = MemoryStorage> {
+    /// Wire Transport
+    transport: T,
+
+    /// Network Selector
+    network: Option<Network>,
+
+    /// Sync Checkpoint Storage
+    storage: S,
+}
+
+impl ClientBuilder<HttpTransport, MemoryStorage> {
+    /// Starts building a [`Client`] that connects to `server_url` over the default
+    /// [`HttpTransport`], with an in-memory [`Storage`] backend.
+    #[inline]
+    pub fn new<U>(server_url: U) -> Result<Self, Error>
+    where
+        U: IntoUrl,
+    {
+        Ok(Self {
+            transport: HttpTransport::new(server_url)?,
+            network: None,
+            storage: MemoryStorage::default(),
+        })
+    }
+}
+
+impl<T, S> ClientBuilder<T, S>
+where
+    T: Transport,
+    S: Storage,
+{
+    /// Sets the network that the built [`Client`] will use to wrap its requests.
+    #[inline]
+    pub fn network(mut self, network: Network) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    /// Sets the [`Transport`] that the built [`Client`] will send requests over, replacing
+    /// whatever transport was configured previously.
+    #[inline]
+    pub fn transport<U>(self, transport: U) -> ClientBuilder<U, S>
+    where
+        U: Transport,
+    {
+        ClientBuilder {
+            transport,
+            network: self.network,
+            storage: self.storage,
+        }
+    }
+
+    /// Sets the [`Storage`] backend that the built [`Client`] will use to persist its sync
+    /// checkpoint, replacing whatever backend was configured previously.
+    #[inline]
+    pub fn storage<U>(self, storage: U) -> ClientBuilder<T, U>
+    where
+        U: Storage,
+    {
+        ClientBuilder {
+            transport: self.transport,
+            network: self.network,
+            storage,
+        }
+    }
+
+    /// Builds the [`Client`] from the configuration accumulated so far.
+    #[inline]
+    pub fn build(self) -> Result<Client<T, S>, Error> {
+        Ok(Client {
+            transport: self.transport,
+            network: self.network,
+            storage: self.storage,
+        })
+    }
+}
@@ -0,0 +1,457 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Mixnet Signer Transport
+//!
+//! manta-pay keeps the *contents* of a `sign`/`sync` request private, but a plain
+//! [`HttpTransport`](super::transport::HttpTransport) still leaks network-level metadata: the
+//! signer sees the wallet's source IP, and an observer on the path sees request timing and size.
+//! [`MixnetTransport`] routes each request through a sequence of mix nodes using layered
+//! (Sphinx-style) onion encryption so that only the first hop learns the client's address and
+//! only the last hop learns the signer's address, with fixed-size padding and a random delay
+//! before each request is sent, so packet sizes and timing cannot be correlated. No cover
+//! traffic (dummy packets) is sent.
+//!
+//! [`MixnetTransport`] is currently send-only: [`MixnetTransport::seal_layer`] generates a fresh ephemeral
+//! key per layer and discards it once the layer is sealed, so no key material survives to decrypt
+//! a reply even from a cooperating mix-node network. Returning the first hop's raw HTTP response
+//! as though it were a genuine signer reply would silently misinterpret garbage, so
+//! [`Transport::send`] fails every call with [`MixnetError::ReplyNotSupported`] after a successful
+//! delivery instead. Genuine round trips need Sphinx SURBs (reply blocks carried in the onion that
+//! let a hop route a response back without learning the client's address), which is future work.
+
+use crate::signer::client::transport::{HttpTransport, Transport, TransportError};
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    fmt::{self, Display, Formatter},
+    time::Duration,
+};
+use manta_util::future::LocalBoxFutureResult;
+use rand::{seq::SliceRandom, Rng};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+/// Fixed Sphinx Packet Size
+///
+/// Every onion packet sent to a hop is padded up to this many bytes so that packet size alone
+/// cannot reveal which layer a hop is peeling or how large the underlying request is.
+pub const PACKET_SIZE: usize = 16 * 1024;
+
+/// Bytes of Ephemeral Public Key Prepended to Every Layer Sealed by [`MixnetTransport::seal_layer`]
+const EPHEMERAL_PUBLIC_KEY_LEN: usize = 32;
+
+/// Bytes of Nonce Prepended to Every Layer Sealed by [`MixnetTransport::seal_layer`]
+const NONCE_LEN: usize = 24;
+
+/// Bytes of AEAD Authentication Tag Appended to Every Layer Sealed by [`MixnetTransport::seal_layer`]
+const TAG_LEN: usize = 16;
+
+/// Total Non-Header Overhead Added by [`MixnetTransport::seal_layer`] to a Single Layer
+///
+/// [`EPHEMERAL_PUBLIC_KEY_LEN`] + [`NONCE_LEN`] + [`TAG_LEN`], independent of the plaintext being
+/// sealed. [`MixnetTransport::build_onion`] additionally pays a per-layer header cost that depends
+/// on the next hop's URL length; see [`MixnetTransport::onion_overhead`].
+const AEAD_OVERHEAD: usize = EPHEMERAL_PUBLIC_KEY_LEN + NONCE_LEN + TAG_LEN;
+
+/// Mixnet Routing Error
+#[derive(Debug)]
+pub enum MixnetError {
+    /// Directory of Candidate Mix Nodes was Empty
+    EmptyDirectory,
+
+    /// Directory Did Not Contain Enough Distinct Nodes for the Requested Hop Count
+    NotEnoughHops {
+        /// Requested Number of Hops
+        requested: usize,
+
+        /// Number of Nodes Available in the Directory
+        available: usize,
+    },
+
+    /// Request Body was Too Large to Fit in a Single Padded [`PACKET_SIZE`] Packet
+    PayloadTooLarge {
+        /// Maximum Payload Size for the Chosen Route
+        max: usize,
+    },
+
+    /// Assembled Packet Did Not Match [`PACKET_SIZE`]
+    ///
+    /// Indicates a bug in [`MixnetTransport::build_onion`]'s overhead accounting rather than a
+    /// caller error: the padded payload plus the route's exact per-hop overhead should always sum
+    /// to exactly [`PACKET_SIZE`].
+    PacketSizeMismatch {
+        /// Expected Packet Size
+        expected: usize,
+
+        /// Actual Assembled Packet Size
+        actual: usize,
+    },
+
+    /// Delivery to the First Hop Failed
+    Delivery(manta_util::http::reqwest::Error),
+
+    /// Reply Received from a Send-Only Transport
+    ///
+    /// See the [module-level documentation](self) for why [`MixnetTransport`] cannot yet decrypt
+    /// a genuine reply.
+    ReplyNotSupported,
+}
+
+impl Display for MixnetError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::EmptyDirectory => write!(f, "mix node directory is empty"),
+            Self::NotEnoughHops {
+                requested,
+                available,
+            } => write!(
+                f,
+                "requested a {requested}-hop route but the directory only has {available} node(s)"
+            ),
+            Self::PayloadTooLarge { max } => {
+                write!(f, "onion payload exceeds the maximum size of {max} bytes")
+            }
+            Self::PacketSizeMismatch { expected, actual } => write!(
+                f,
+                "assembled onion packet was {actual} bytes, expected exactly {expected}"
+            ),
+            Self::Delivery(err) => write!(f, "failed to reach first hop: {err}"),
+            Self::ReplyNotSupported => write!(
+                f,
+                "mixnet transport is send-only and cannot decrypt a reply"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MixnetError {}
+
+/// Mix Node Descriptor
+#[derive(Clone)]
+pub struct MixNode {
+    /// Hop's Onion Public Key
+    pub public_key: PublicKey,
+
+    /// URL the Hop Listens On
+    pub url: manta_util::http::reqwest::Url,
+}
+
+impl MixNode {
+    /// Builds a new [`MixNode`] description from its `public_key` and listening `url`.
+    #[inline]
+    pub fn new(public_key: PublicKey, url: manta_util::http::reqwest::Url) -> Self {
+        Self { public_key, url }
+    }
+}
+
+/// Send Delay Configuration
+///
+/// Before each request is delivered to its first hop, [`MixnetTransport`] waits a random
+/// duration drawn between `min_delay` and `max_delay`. This is one delay per request, applied by
+/// the client; no cover traffic is sent.
+#[derive(Clone, Copy, Debug)]
+pub struct DelayConfig {
+    /// Minimum Delay Before Delivery
+    pub min_delay: Duration,
+
+    /// Maximum Delay Before Delivery
+    pub max_delay: Duration,
+}
+
+impl Default for DelayConfig {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            min_delay: Duration::from_millis(0),
+            max_delay: Duration::from_millis(200),
+        }
+    }
+}
+
+/// Mixnet Signer Transport
+///
+/// Routes every [`Transport::send`] call through a fresh, randomly chosen route of mix nodes
+/// rather than connecting to the signer directly. Send-only: every call fails with
+/// [`MixnetError::ReplyNotSupported`] after a successful delivery, since there is no reply path
+/// yet. See the [module-level documentation](self) for more information.
+pub struct MixnetTransport {
+    /// Candidate Mix Nodes
+    directory: Vec<MixNode>,
+
+    /// Number of Hops Per Route
+    hop_count: usize,
+
+    /// Real Signer Endpoint, Known Only to the Final Hop
+    destination: manta_util::http::reqwest::Url,
+
+    /// Send Delay Configuration
+    delay: DelayConfig,
+}
+
+impl MixnetTransport {
+    /// Builds a new [`MixnetTransport`] that delivers to `destination` (the real signer URL)
+    /// through `hop_count` randomly chosen nodes out of `directory`.
+    ///
+    /// Fails with [`MixnetError::EmptyDirectory`] or [`MixnetError::NotEnoughHops`] if `directory`
+    /// cannot supply `hop_count` distinct nodes for every future route.
+    #[inline]
+    pub fn new(
+        directory: Vec<MixNode>,
+        hop_count: usize,
+        destination: manta_util::http::reqwest::Url,
+    ) -> Result<Self, MixnetError> {
+        if directory.is_empty() {
+            return Err(MixnetError::EmptyDirectory);
+        }
+        if hop_count > directory.len() {
+            return Err(MixnetError::NotEnoughHops {
+                requested: hop_count,
+                available: directory.len(),
+            });
+        }
+        Ok(Self {
+            directory,
+            hop_count,
+            destination,
+            delay: DelayConfig::default(),
+        })
+    }
+
+    /// Overrides the default cover-traffic delay configuration.
+    #[inline]
+    pub fn with_delay(mut self, delay: DelayConfig) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Picks a random route of [`Self::hop_count`] distinct mix nodes from the directory.
+    ///
+    /// [`Self::new`] already guarantees `directory.len() >= hop_count`, but this is checked again
+    /// defensively so a route is never silently shorter than requested.
+    fn choose_route(&self) -> Result<Vec<MixNode>, MixnetError> {
+        let route: Vec<MixNode> = self
+            .directory
+            .choose_multiple(&mut rand::thread_rng(), self.hop_count)
+            .cloned()
+            .collect();
+        if route.len() < self.hop_count {
+            return Err(MixnetError::NotEnoughHops {
+                requested: self.hop_count,
+                available: route.len(),
+            });
+        }
+        Ok(route)
+    }
+
+    /// Encrypts `payload` for `hop`, returning the sealed bytes for that single layer.
+    fn seal_layer(hop: &MixNode, payload: &[u8]) -> Vec<u8> {
+        use chacha20poly1305::{aead::Aead, KeyInit, XChaCha20Poly1305, XNonce};
+
+        let ephemeral = StaticSecret::new(rand::thread_rng());
+        let ephemeral_public = PublicKey::from(&ephemeral);
+        let shared_secret = ephemeral.diffie_hellman(&hop.public_key);
+        let cipher = XChaCha20Poly1305::new(shared_secret.as_bytes().into());
+        let mut nonce_bytes = [0u8; 24];
+        rand::thread_rng().fill(&mut nonce_bytes);
+        let nonce = XNonce::from_slice(&nonce_bytes);
+        let mut ciphertext = cipher
+            .encrypt(nonce, payload)
+            .expect("Encryption with a fixed-size key and nonce cannot fail.");
+        let mut out = Vec::with_capacity(EPHEMERAL_PUBLIC_KEY_LEN + NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(ephemeral_public.as_bytes());
+        out.extend_from_slice(&nonce_bytes);
+        out.append(&mut ciphertext);
+        out
+    }
+
+    /// Computes the exact total overhead [`Self::build_onion`] adds on top of the padded payload
+    /// for `route`, by walking the same innermost-to-outermost layering order `build_onion` uses
+    /// instead of assuming a flat per-hop cost.
+    ///
+    /// Every layer costs [`AEAD_OVERHEAD`] plus a one-byte marker, and every layer except the
+    /// innermost one also carries the next hop's URL (the address the *current* layer must
+    /// forward to), so the overhead depends on the chosen route's actual URLs, not just its
+    /// length.
+    fn onion_overhead(route: &[MixNode]) -> usize {
+        let mut next_hop_url_len = None;
+        let mut overhead = 0usize;
+        for hop in route.iter().rev() {
+            let header_len = match next_hop_url_len.take() {
+                Some(len) => len + 1,
+                None => 1,
+            };
+            overhead += AEAD_OVERHEAD + header_len;
+            next_hop_url_len = Some(hop.url.as_str().len());
+        }
+        overhead
+    }
+
+    /// Wraps `body`, destined for [`Self::destination`], in one nested encryption layer per hop
+    /// in `route`, padding the innermost layer so the assembled packet is exactly [`PACKET_SIZE`]
+    /// bytes, indistinguishable in size from any other request.
+    ///
+    /// Fails with [`MixnetError::PayloadTooLarge`] if `body` does not fit in the fixed packet
+    /// budget once `route`'s exact overhead (see [`Self::onion_overhead`]) is subtracted, rather
+    /// than silently truncating it, and with [`MixnetError::PacketSizeMismatch`] if the assembled
+    /// packet still does not come out to exactly [`PACKET_SIZE`].
+    fn build_onion(&self, route: &[MixNode], body: Vec<u8>) -> Result<Vec<u8>, MixnetError> {
+        struct NextHop {
+            url: Option<manta_util::http::reqwest::Url>,
+            body: Vec<u8>,
+        }
+
+        let max = PACKET_SIZE.saturating_sub(Self::onion_overhead(route));
+        if body.len() > max {
+            return Err(MixnetError::PayloadTooLarge { max });
+        }
+        let mut layer = NextHop {
+            url: None,
+            body: {
+                let mut padded = body;
+                padded.resize(max, 0);
+                padded
+            },
+        };
+        for hop in route.iter().rev() {
+            let mut plaintext = Vec::new();
+            match layer.url.take() {
+                Some(next) => {
+                    plaintext.extend_from_slice(next.as_str().as_bytes());
+                    plaintext.push(0);
+                }
+                None => plaintext.push(1),
+            }
+            plaintext.extend_from_slice(&layer.body);
+            layer = NextHop {
+                url: Some(hop.url.clone()),
+                body: Self::seal_layer(hop, &plaintext),
+            };
+        }
+        if layer.body.len() != PACKET_SIZE {
+            return Err(MixnetError::PacketSizeMismatch {
+                expected: PACKET_SIZE,
+                actual: layer.body.len(),
+            });
+        }
+        Ok(layer.body)
+    }
+}
+
+impl Transport for MixnetTransport {
+    /// Delivers `command`/`body` through a fresh onion route.
+    ///
+    /// This transport cannot yet decrypt a reply (see the [module-level documentation](self)), so
+    /// a successful delivery still resolves to [`MixnetError::ReplyNotSupported`] rather than
+    /// handing the first hop's raw response back to the caller as though it were a genuine signer
+    /// reply.
+    #[inline]
+    fn send(&self, command: &str, body: Vec<u8>) -> LocalBoxFutureResult<Vec<u8>, TransportError> {
+        let delay = self.delay;
+        let result = self.choose_route().and_then(|route| {
+            // The outer onion layer is sealed for `route[0]`, so the packet must be POSTed to
+            // that hop specifically, not to whichever node happened to be chosen last time.
+            let delivery = HttpTransport::new(route[0].url.clone()).map_err(MixnetError::Delivery)?;
+            let mut envelope = Vec::new();
+            envelope.extend_from_slice(
+                self.destination
+                    .join(command)
+                    .expect("Invalid command name.")
+                    .as_str()
+                    .as_bytes(),
+            );
+            envelope.push(0);
+            envelope.extend_from_slice(&body);
+            let packet = self.build_onion(&route, envelope)?;
+            Ok((delivery, packet))
+        });
+        Box::pin(async move {
+            let (delivery, packet) = result.map_err(TransportError::from)?;
+            let jitter = rand::thread_rng().gen_range(delay.min_delay..=delay.max_delay);
+            #[cfg(feature = "std")]
+            tokio::time::sleep(jitter).await;
+            #[cfg(not(feature = "std"))]
+            let _ = jitter;
+            delivery.send("mix", packet).await?;
+            Err(TransportError::from(MixnetError::ReplyNotSupported))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mix_node(url: &str) -> MixNode {
+        let secret = StaticSecret::new(rand::thread_rng());
+        MixNode::new(
+            PublicKey::from(&secret),
+            manta_util::http::reqwest::Url::parse(url).unwrap(),
+        )
+    }
+
+    fn route() -> Vec<MixNode> {
+        Vec::from([
+            mix_node("https://hop-one.example/"),
+            mix_node("https://hop-two.example/"),
+            mix_node("https://hop-three.example/"),
+        ])
+    }
+
+    #[test]
+    fn build_onion_assembles_exactly_packet_size() {
+        let route = route();
+        let transport = MixnetTransport::new(
+            route.clone(),
+            route.len(),
+            manta_util::http::reqwest::Url::parse("https://signer.example/").unwrap(),
+        )
+        .unwrap();
+        let packet = transport
+            .build_onion(&route, Vec::from(*b"request body"))
+            .unwrap();
+        assert_eq!(packet.len(), PACKET_SIZE);
+    }
+
+    #[test]
+    fn build_onion_rejects_a_payload_that_does_not_fit_the_budget() {
+        let route = route();
+        let transport = MixnetTransport::new(
+            route.clone(),
+            route.len(),
+            manta_util::http::reqwest::Url::parse("https://signer.example/").unwrap(),
+        )
+        .unwrap();
+        let max = PACKET_SIZE - MixnetTransport::onion_overhead(&route);
+        let oversized = vec![0u8; max + 1];
+        assert!(matches!(
+            transport.build_onion(&route, oversized),
+            Err(MixnetError::PayloadTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn onion_overhead_accounts_for_real_url_lengths_not_a_flat_per_hop_cost() {
+        let short_route = Vec::from([mix_node("https://a.example/")]);
+        let long_route = Vec::from([mix_node(
+            "https://a-much-longer-mix-node-hostname.example/",
+        )]);
+        assert!(
+            MixnetTransport::onion_overhead(&long_route)
+                > MixnetTransport::onion_overhead(&short_route)
+        );
+    }
+}
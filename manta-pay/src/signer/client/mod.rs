@@ -0,0 +1,47 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Signer Client Implementations
+
+pub mod http;
+pub mod storage;
+pub mod transport;
+pub mod warp;
+
+#[cfg(feature = "compression")]
+pub mod compression;
+
+#[cfg(feature = "multi-thread")]
+pub mod send;
+
+#[cfg(feature = "websocket")]
+pub mod websocket;
+
+#[cfg(feature = "quic")]
+pub mod quic;
+
+#[cfg(feature = "mixnet")]
+pub mod mixnet;
+
+#[doc(inline)]
+pub use http::{Client, ClientBuilder, Wallet};
+
+#[doc(inline)]
+pub use warp::{WarpSyncError, WarpSyncRequest, WarpSyncResponse, WarpSyncVerifier};
+
+#[cfg(feature = "multi-thread")]
+#[doc(inline)]
+pub use send::SendClient;
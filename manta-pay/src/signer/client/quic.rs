@@ -0,0 +1,70 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! QUIC Signer Transport
+//!
+//! Like [`WebSocketTransport`](super::websocket::WebSocketTransport), this keeps a single
+//! connection to the signer open for the lifetime of the wallet, but relies on QUIC's cheap
+//! stream multiplexing instead of an application-level request id: every [`Transport::send`]
+//! call opens its own bidirectional stream, writes the encoded command, and reads the response
+//! until the peer closes its side of the stream.
+
+use crate::signer::client::transport::{Transport, TransportError};
+use alloc::{boxed::Box, vec::Vec};
+use manta_util::future::LocalBoxFutureResult;
+use quinn::Connection;
+
+/// QUIC Signer Transport
+pub struct QuicTransport {
+    /// Underlying QUIC Connection
+    connection: Connection,
+}
+
+impl QuicTransport {
+    /// Builds a new [`QuicTransport`] from an already-established `connection` to the signer.
+    #[inline]
+    pub fn new(connection: Connection) -> Self {
+        Self { connection }
+    }
+}
+
+impl Transport for QuicTransport {
+    #[inline]
+    fn send(&self, command: &str, body: Vec<u8>) -> LocalBoxFutureResult<Vec<u8>, TransportError> {
+        let command = command.as_bytes().to_vec();
+        let connection = self.connection.clone();
+        Box::pin(async move {
+            let to_quic_error = |err: alloc::string::String| TransportError::Quic(err);
+            let (mut send, mut recv) = connection
+                .open_bi()
+                .await
+                .map_err(|err| to_quic_error(err.to_string()))?;
+            send.write_all(&(command.len() as u16).to_le_bytes())
+                .await
+                .map_err(|err| to_quic_error(err.to_string()))?;
+            send.write_all(&command)
+                .await
+                .map_err(|err| to_quic_error(err.to_string()))?;
+            send.write_all(&body)
+                .await
+                .map_err(|err| to_quic_error(err.to_string()))?;
+            send.finish().map_err(|err| to_quic_error(err.to_string()))?;
+            recv.read_to_end(16 * 1024 * 1024)
+                .await
+                .map_err(|err| to_quic_error(err.to_string()))
+        })
+    }
+}
@@ -0,0 +1,162 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! `Send`-Capable Signer Client
+//!
+//! [`Client`] implements [`signer::Connection`](manta_accounting::wallet::signer::Connection),
+//! whose futures are required to be `LocalBoxFutureResult`s so that the trait stays usable from
+//! single-threaded and `!Send` executors (e.g. WASM). That requirement, however, pins every
+//! wallet driven through [`Client`] to a single thread. [`SendClient`] wraps [`Client`] with an
+//! inherent API returning `Send + Sync` futures instead, so that a server hosting many wallets
+//! can drive their sign/sync calls concurrently on a work-stealing multi-threaded runtime.
+
+use crate::signer::{
+    client::{
+        http::{Client, Error},
+        storage::Storage,
+        transport::SendTransport,
+        warp::{WarpSyncError, WarpSyncVerifier},
+    },
+    Checkpoint, GetRequest, IdentityRequest, IdentityResponse, SignError, SignRequest,
+    SignResponse, SignWithTransactionDataResult, SyncError, SyncRequest, SyncResponse,
+    TransactionDataRequest, TransactionDataResponse,
+};
+use alloc::boxed::Box;
+use manta_util::future::BoxFutureResult;
+
+/// `Send`-Capable Signer Client
+///
+/// See the [module-level documentation](self) for more information.
+pub struct SendClient<T, S> {
+    /// Inner Client
+    client: Client<T, S>,
+}
+
+impl<T, S> SendClient<T, S>
+where
+    T: SendTransport + Send + Sync,
+    S: Storage + Send + Sync,
+    S::Error: Send,
+{
+    /// Builds a new [`SendClient`] from an existing [`Client`].
+    #[inline]
+    pub fn new(client: Client<T, S>) -> Self {
+        Self { client }
+    }
+
+    /// Returns the inner [`Client`], discarding the `Send`-capable wrapper.
+    #[inline]
+    pub fn into_inner(self) -> Client<T, S> {
+        self.client
+    }
+
+    /// Sends a `sync` request to the signer, persisting its checkpoint on success.
+    #[inline]
+    pub fn sync(
+        &mut self,
+        request: SyncRequest,
+    ) -> BoxFutureResult<Result<SyncResponse, SyncError>, Error> {
+        Box::pin(self.client.sync_request_send("sync", request))
+    }
+
+    /// Sends an `sbt_sync` request to the signer, persisting its checkpoint on success.
+    #[inline]
+    pub fn sbt_sync(
+        &mut self,
+        request: SyncRequest,
+    ) -> BoxFutureResult<Result<SyncResponse, SyncError>, Error> {
+        Box::pin(self.client.sync_request_send("sbt_sync", request))
+    }
+
+    /// Sends an `initial_sync` request to the signer, persisting its checkpoint on success.
+    #[inline]
+    pub fn initial_sync(
+        &mut self,
+        request: crate::signer::InitialSyncRequest,
+    ) -> BoxFutureResult<Result<SyncResponse, SyncError>, Error> {
+        Box::pin(self.client.initial_sync_request_send(request))
+    }
+
+    /// Sends a `sign` request to the signer.
+    #[inline]
+    pub fn sign(
+        &mut self,
+        request: SignRequest,
+    ) -> BoxFutureResult<Result<SignResponse, SignError>, Error> {
+        Box::pin(self.client.post_request_send("sign", request))
+    }
+
+    /// Requests the receiving address from the signer.
+    #[inline]
+    pub fn address(&mut self) -> BoxFutureResult<Option<crate::config::utxo::Address>, Error> {
+        Box::pin(self.client.post_request_send("address", GetRequest::Get))
+    }
+
+    /// Requests the transaction data associated to `request` from the signer.
+    #[inline]
+    pub fn transaction_data(
+        &mut self,
+        request: TransactionDataRequest,
+    ) -> BoxFutureResult<TransactionDataResponse, Error> {
+        Box::pin(self.client.post_request_send("transaction_data", request))
+    }
+
+    /// Requests an identity proof from the signer.
+    #[inline]
+    pub fn identity_proof(
+        &mut self,
+        request: IdentityRequest,
+    ) -> BoxFutureResult<IdentityResponse, Error> {
+        Box::pin(self.client.post_request_send("identity", request))
+    }
+
+    /// Sends a `sign_with_transaction_data` request to the signer.
+    #[inline]
+    pub fn sign_with_transaction_data(
+        &mut self,
+        request: SignRequest,
+    ) -> BoxFutureResult<SignWithTransactionDataResult, Error> {
+        Box::pin(
+            self.client
+                .post_request_send("sign_with_transaction_data", request),
+        )
+    }
+
+    /// Requests the transfer parameters from the signer.
+    #[inline]
+    pub fn transfer_parameters(&mut self) -> BoxFutureResult<crate::config::Parameters, Error> {
+        Box::pin(
+            self.client
+                .post_request_send("transfer_parameters", GetRequest::Get),
+        )
+    }
+
+    /// Performs a warp sync to `trusted`, independently checking the signer's response with
+    /// `verifier` before adopting it. See
+    /// [`Client::warp_sync`](crate::signer::client::http::Client::warp_sync) for more
+    /// information.
+    #[inline]
+    pub fn warp_sync<V>(
+        &mut self,
+        trusted: Checkpoint,
+        verifier: V,
+    ) -> BoxFutureResult<SyncResponse, WarpSyncError>
+    where
+        V: WarpSyncVerifier + Send + 'static,
+    {
+        Box::pin(self.client.warp_sync_send(trusted, verifier))
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Client-Side Sync Storage
+//!
+//! This module defines the [`Storage`] abstraction consulted by [`Client`](super::http::Client)
+//! before issuing a sync request to the signer, and updated after each successful response, so
+//! that a restarted wallet can resume from its last checkpoint instead of replaying the entire
+//! sync history from genesis.
+
+use crate::signer::{Checkpoint, SyncResponse};
+use alloc::{boxed::Box, vec::Vec};
+use manta_util::future::LocalBoxFutureResult;
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+#[cfg(feature = "std")]
+use std::{
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+/// Persisted Sync State
+///
+/// The most recently adopted [`Checkpoint`] together with the single [`SyncResponse`] that it
+/// came from. Each store replaces the previous state; earlier responses are not merged into it.
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SyncState {
+    /// Checkpoint
+    pub checkpoint: Checkpoint,
+
+    /// Sync Response
+    pub response: SyncResponse,
+}
+
+/// Client-Side Sync Storage
+///
+/// An implementation of this trait is consulted by [`Client`](super::http::Client) before
+/// issuing a `sync`-family request to the signer, and is written back to after each successful
+/// response completes, allowing sync to resume from the stored [`SyncState`] instead of from
+/// genesis.
+pub trait Storage {
+    /// Storage Error Type
+    type Error;
+
+    /// Loads the most recently stored [`SyncState`], returning `None` if nothing has been stored
+    /// yet.
+    fn load(&self) -> LocalBoxFutureResult<Option<SyncState>, Self::Error>;
+
+    /// Stores `state` as the most recently adopted sync state, overwriting whatever was stored
+    /// previously.
+    fn store(&mut self, state: SyncState) -> LocalBoxFutureResult<(), Self::Error>;
+}
+
+/// In-Memory Storage
+///
+/// An implementation of [`Storage`] that keeps the [`SyncState`] resident in memory for the
+/// lifetime of the process. This is the default storage backend, preserving the existing
+/// re-sync-from-genesis-on-restart behavior while still giving in-process callers (e.g. multiple
+/// [`Wallet`](super::http::Wallet)s sharing a [`Client`](super::http::Client)) a single source of
+/// truth for the checkpoint.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStorage {
+    /// Stored State
+    state: Option<SyncState>,
+}
+
+impl Storage for MemoryStorage {
+    type Error = core::convert::Infallible;
+
+    #[inline]
+    fn load(&self) -> LocalBoxFutureResult<Option<SyncState>, Self::Error> {
+        let state = self.state.clone();
+        Box::pin(async move { Ok(state) })
+    }
+
+    #[inline]
+    fn store(&mut self, state: SyncState) -> LocalBoxFutureResult<(), Self::Error> {
+        self.state = Some(state);
+        Box::pin(async move { Ok(()) })
+    }
+}
+
+/// File-Backed Encrypted Storage
+///
+/// An implementation of [`Storage`] that persists the [`SyncState`] to a file, encrypting its
+/// serialized bytes at rest with XChaCha20-Poly1305 under a caller-supplied key so that a stolen
+/// disk image does not leak the wallet's UTXO membership progress.
+#[cfg(feature = "std")]
+pub struct FileStorage {
+    /// Path to the backing file
+    path: PathBuf,
+
+    /// Symmetric Encryption Key
+    key: [u8; 32],
+}
+
+#[cfg(feature = "std")]
+impl FileStorage {
+    /// Builds a new [`FileStorage`] that persists to `path`, encrypting its contents with `key`.
+    #[inline]
+    pub fn new<P>(path: P, key: [u8; 32]) -> Self
+    where
+        P: Into<PathBuf>,
+    {
+        Self {
+            path: path.into(),
+            key,
+        }
+    }
+
+    /// Returns the path that `self` reads from and writes to.
+    #[inline]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Encrypts `plaintext` under `key`, prefixing the output with the random nonce used to seal
+    /// it.
+    ///
+    /// Free function (rather than a `&self` method) so it can be called from inside the
+    /// [`spawn_blocking`](tokio::task::spawn_blocking) closures in [`Storage for FileStorage`]
+    /// without capturing a borrow of `self`.
+    fn seal(key: &[u8; 32], plaintext: &[u8]) -> Vec<u8> {
+        use chacha20poly1305::{aead::Aead, KeyInit, XChaCha20Poly1305, XNonce};
+        use rand::RngCore;
+
+        let cipher = XChaCha20Poly1305::new(key.as_slice().into());
+        let mut nonce_bytes = [0u8; 24];
+        rand::thread_rng().fill_bytes(&mut nonce_bytes);
+        let nonce = XNonce::from_slice(&nonce_bytes);
+        let mut ciphertext = cipher
+            .encrypt(nonce, plaintext)
+            .expect("Encryption with a fixed-size key and nonce cannot fail.");
+        let mut out = nonce_bytes.to_vec();
+        out.append(&mut ciphertext);
+        out
+    }
+
+    /// Decrypts `sealed` under `key`, interpreting its first 24 bytes as the nonce it was sealed
+    /// with. See [`Self::seal`] for why this takes `key` rather than `&self`.
+    fn open(key: &[u8; 32], sealed: &[u8]) -> io::Result<Vec<u8>> {
+        use chacha20poly1305::{aead::Aead, KeyInit, XChaCha20Poly1305, XNonce};
+
+        if sealed.len() < 24 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "storage file is too short to contain a nonce",
+            ));
+        }
+        let (nonce_bytes, ciphertext) = sealed.split_at(24);
+        let cipher = XChaCha20Poly1305::new(key.as_slice().into());
+        cipher
+            .decrypt(XNonce::from_slice(nonce_bytes), ciphertext)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "unable to decrypt storage file"))
+    }
+
+    /// Blocking implementation behind [`Storage::load`], run on a blocking-pool thread via
+    /// [`spawn_blocking`](tokio::task::spawn_blocking) so that a disk read plus AEAD open never
+    /// stalls a worker thread another wallet's `sign`/`sync` call is scheduled on.
+    fn load_blocking(path: &Path, key: &[u8; 32]) -> io::Result<Option<SyncState>> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let mut sealed = Vec::new();
+        std::fs::File::open(path)?.read_to_end(&mut sealed)?;
+        let plaintext = Self::open(key, &sealed)?;
+        let state = manta_util::serde_json::from_slice(&plaintext)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        Ok(Some(state))
+    }
+
+    /// Blocking implementation behind [`Storage::store`]. See [`Self::load_blocking`].
+    fn store_blocking(path: &Path, key: &[u8; 32], state: &SyncState) -> io::Result<()> {
+        let plaintext = manta_util::serde_json::to_vec(state)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let sealed = Self::seal(key, &plaintext);
+        std::fs::File::create(path)?.write_all(&sealed)?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl Storage for FileStorage {
+    type Error = io::Error;
+
+    #[inline]
+    fn load(&self) -> LocalBoxFutureResult<Option<SyncState>, Self::Error> {
+        let path = self.path.clone();
+        let key = self.key;
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || Self::load_blocking(&path, &key))
+                .await
+                .expect("The blocking sync-state load task should not panic.")
+        })
+    }
+
+    #[inline]
+    fn store(&mut self, state: SyncState) -> LocalBoxFutureResult<(), Self::Error> {
+        let path = self.path.clone();
+        let key = self.key;
+        Box::pin(async move {
+            tokio::task::spawn_blocking(move || Self::store_blocking(&path, &key, &state))
+                .await
+                .expect("The blocking sync-state store task should not panic.")
+        })
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seal_then_open_recovers_plaintext() {
+        let key = [1; 32];
+        let plaintext = b"sync state bytes";
+        let sealed = FileStorage::seal(&key, plaintext);
+        assert_eq!(FileStorage::open(&key, &sealed).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn open_rejects_wrong_key_and_tampered_ciphertext() {
+        let key = [1; 32];
+        let sealed = FileStorage::seal(&key, b"sync state bytes");
+        assert!(FileStorage::open(&[2; 32], &sealed).is_err());
+        let mut tampered = sealed.clone();
+        let last = tampered.len() - 1;
+        tampered[last] ^= 0xff;
+        assert!(FileStorage::open(&key, &tampered).is_err());
+    }
+
+    #[tokio::test]
+    async fn file_storage_round_trips_through_disk() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("manta-pay-file-storage-test-{}", rand::random::<u64>()));
+        let mut storage = FileStorage::new(path.clone(), [7; 32]);
+        assert_eq!(storage.load().await.unwrap(), None);
+        let state = SyncState::default();
+        storage.store(state.clone()).await.unwrap();
+        assert_eq!(storage.load().await.unwrap(), Some(state));
+        let _ = std::fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,235 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pluggable Signer Wire Transport
+//!
+//! [`Client`](super::http::Client) used to hard-code a single request/response round trip over
+//! `reqwest` POST. [`Transport`] pulls that wire format out behind a trait so that [`Client`] can
+//! be generic over how a serialized command reaches the signer: the default [`HttpTransport`]
+//! keeps the original one-shot POST behavior, while other implementations (e.g. a persistent,
+//! multiplexed WebSocket connection) keep a single connection open across calls. Every call
+//! still yields exactly one response body; the trait has no way to deliver server-pushed
+//! incremental responses.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::fmt::{self, Display, Formatter};
+use manta_util::{
+    future::{BoxFutureResult, LocalBoxFutureResult},
+    http::reqwest::{self, header, IntoUrl, Url},
+};
+
+#[cfg(feature = "compression")]
+use crate::signer::client::compression::{Codec, CompressionConfig};
+
+/// Transport-Level Error
+#[derive(Debug)]
+pub enum TransportError {
+    /// HTTP Transport Error
+    Http(reqwest::Error),
+
+    /// Message Encoding/Decoding Error
+    Codec(manta_util::serde_json::Error),
+
+    /// WebSocket Transport Error
+    #[cfg(feature = "websocket")]
+    WebSocket(tokio_tungstenite::tungstenite::Error),
+
+    /// QUIC Transport Error
+    #[cfg(feature = "quic")]
+    Quic(alloc::string::String),
+
+    /// Body Decompression Error
+    #[cfg(feature = "compression")]
+    Decompression(std::io::Error),
+
+    /// Mixnet Transport Error
+    #[cfg(feature = "mixnet")]
+    Mixnet(crate::signer::client::mixnet::MixnetError),
+}
+
+impl Display for TransportError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Http(err) => write!(f, "HTTP transport error: {err}"),
+            Self::Codec(err) => write!(f, "message encoding error: {err}"),
+            #[cfg(feature = "websocket")]
+            Self::WebSocket(err) => write!(f, "WebSocket transport error: {err}"),
+            #[cfg(feature = "quic")]
+            Self::Quic(err) => write!(f, "QUIC transport error: {err}"),
+            #[cfg(feature = "compression")]
+            Self::Decompression(err) => write!(f, "failed to decompress response body: {err}"),
+            #[cfg(feature = "mixnet")]
+            Self::Mixnet(err) => write!(f, "mixnet transport error: {err}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TransportError {}
+
+impl From<reqwest::Error> for TransportError {
+    #[inline]
+    fn from(err: reqwest::Error) -> Self {
+        Self::Http(err)
+    }
+}
+
+impl From<manta_util::serde_json::Error> for TransportError {
+    #[inline]
+    fn from(err: manta_util::serde_json::Error) -> Self {
+        Self::Codec(err)
+    }
+}
+
+#[cfg(feature = "mixnet")]
+impl From<crate::signer::client::mixnet::MixnetError> for TransportError {
+    #[inline]
+    fn from(err: crate::signer::client::mixnet::MixnetError) -> Self {
+        Self::Mixnet(err)
+    }
+}
+
+/// Signer Wire Transport
+///
+/// Delivers an already-encoded `command` request body to the signer and returns its
+/// already-encoded response body. [`Client`](super::http::Client) is responsible for all
+/// serialization/deserialization and network wrapping; implementations of this trait only need
+/// to move bytes to the signer and back.
+pub trait Transport {
+    /// Sends the encoded `body` for `command` to the signer, returning its encoded response.
+    fn send(&self, command: &str, body: Vec<u8>) -> LocalBoxFutureResult<Vec<u8>, TransportError>;
+}
+
+/// `Send`-Capable Signer Wire Transport
+///
+/// [`Transport::send`] returns a `LocalBoxFutureResult` so the trait stays usable from `!Send`
+/// executors, but that means its future can never be proven `Send`, even for an implementation
+/// like [`HttpTransport`] whose own work is `Send` end to end. Implementations that want to be
+/// usable through [`SendClient`](super::send::SendClient) implement this trait as well,
+/// exposing the same request over a future that actually is `Send`.
+pub trait SendTransport: Transport {
+    /// Sends the encoded `body` for `command` to the signer, returning its encoded response over
+    /// a `Send` future.
+    fn send_send(&self, command: &str, body: Vec<u8>) -> BoxFutureResult<Vec<u8>, TransportError>;
+}
+
+/// Default HTTP Transport
+///
+/// Opens one `reqwest` POST per command, matching the original [`Client`](super::http::Client)
+/// behavior before the transport layer was pulled out.
+#[derive(Clone)]
+pub struct HttpTransport {
+    /// Underlying HTTP Client
+    client: reqwest::Client,
+
+    /// Base Server URL
+    base_url: Url,
+
+    /// Negotiated Body Compression
+    #[cfg(feature = "compression")]
+    compression: CompressionConfig,
+}
+
+impl HttpTransport {
+    /// Builds a new [`HttpTransport`] that posts commands to `server_url`.
+    #[inline]
+    pub fn new<U>(server_url: U) -> Result<Self, reqwest::Error>
+    where
+        U: IntoUrl,
+    {
+        Ok(Self {
+            client: reqwest::Client::new(),
+            base_url: server_url.into_url()?,
+            #[cfg(feature = "compression")]
+            compression: CompressionConfig::default(),
+        })
+    }
+
+    /// Overrides the default negotiated body [`CompressionConfig`].
+    #[cfg(feature = "compression")]
+    #[inline]
+    pub fn with_compression(mut self, compression: CompressionConfig) -> Self {
+        self.compression = compression;
+        self
+    }
+}
+
+impl HttpTransport {
+    /// Shared implementation behind both [`Transport::send`] and [`SendTransport::send_send`].
+    ///
+    /// This future only awaits `Send` sub-futures (the underlying `reqwest` request) and touches
+    /// only `Send + Sync` state, so it can be boxed either way: as a `LocalBoxFutureResult` for
+    /// [`Transport`], or as a `BoxFutureResult` for [`SendTransport`].
+    async fn send_impl(&self, command: &str, body: Vec<u8>) -> Result<Vec<u8>, TransportError> {
+        #[cfg(feature = "compression")]
+        let (body, content_encoding) = {
+            let CompressionConfig { codec, threshold } = self.compression;
+            if codec != Codec::Identity && body.len() >= threshold {
+                (codec.compress(&body), codec.header_value())
+            } else {
+                (body, None)
+            }
+        };
+        let mut builder = self
+            .client
+            .post(self.base_url.join(command).expect("Invalid command name."))
+            .header(header::CONTENT_TYPE, "application/json");
+        #[cfg(feature = "compression")]
+        {
+            builder = builder.header(
+                header::ACCEPT_ENCODING,
+                [Codec::Gzip, Codec::Brotli]
+                    .iter()
+                    .filter_map(|codec| codec.header_value())
+                    .collect::<alloc::vec::Vec<_>>()
+                    .join(", "),
+            );
+            if let Some(encoding) = content_encoding {
+                builder = builder.header(header::CONTENT_ENCODING, encoding);
+            }
+        }
+        let response = builder.body(body).send().await?.error_for_status()?;
+        #[cfg(feature = "compression")]
+        let codec = response
+            .headers()
+            .get(header::CONTENT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .map(Codec::from_header_value)
+            .unwrap_or(Codec::Identity);
+        let bytes = response.bytes().await?;
+        #[cfg(feature = "compression")]
+        {
+            return codec.decompress(&bytes).map_err(TransportError::Decompression);
+        }
+        #[cfg(not(feature = "compression"))]
+        Ok(bytes.to_vec())
+    }
+}
+
+impl Transport for HttpTransport {
+    #[inline]
+    fn send(&self, command: &str, body: Vec<u8>) -> LocalBoxFutureResult<Vec<u8>, TransportError> {
+        Box::pin(self.send_impl(command, body))
+    }
+}
+
+impl SendTransport for HttpTransport {
+    #[inline]
+    fn send_send(&self, command: &str, body: Vec<u8>) -> BoxFutureResult<Vec<u8>, TransportError> {
+        Box::pin(self.send_impl(command, body))
+    }
+}
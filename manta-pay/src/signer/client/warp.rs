@@ -0,0 +1,200 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Warp Sync
+//!
+//! `initial_sync` replays every intermediate UTXO accumulator delta from genesis, which is slow
+//! for a wallet joining a long-lived chain. A warp sync instead submits a recent, externally
+//! trusted [`Checkpoint`] to the signer and asks it to skip straight there: the signer returns
+//! only the accumulator state and membership witnesses needed from that checkpoint onward.
+//! Because the starting checkpoint comes from the caller rather than the signer,
+//! [`Client::warp_sync`](super::http::Client::warp_sync) verifies that the signer actually
+//! started from the claimed checkpoint before adopting the response, so an untrusted server
+//! cannot feed a forged starting state.
+
+use crate::signer::{client::http::Error, Checkpoint, SyncError, SyncResponse};
+use core::fmt::{self, Display, Formatter};
+
+#[cfg(feature = "serde")]
+use manta_util::serde::{Deserialize, Serialize};
+
+/// Warp Sync Request
+///
+/// Asks the signer to skip every delta before `trusted_checkpoint`, returning only the
+/// accumulator state and witnesses needed starting from there.
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug)]
+pub struct WarpSyncRequest {
+    /// Trusted Starting Checkpoint
+    pub trusted_checkpoint: Checkpoint,
+}
+
+/// Warp Sync Response
+///
+/// Pairs the checkpoint that the signer actually started from with the accumulator state and
+/// witnesses computed from it, so the caller can check [`Self::base_checkpoint`] against the
+/// checkpoint it trusted before adopting [`Self::response`].
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize),
+    serde(crate = "manta_util::serde", deny_unknown_fields)
+)]
+#[derive(Clone, Debug)]
+pub struct WarpSyncResponse {
+    /// Checkpoint the Signer Started From
+    pub base_checkpoint: Checkpoint,
+
+    /// Accumulator State and Witnesses from [`Self::base_checkpoint`] Onward
+    pub response: SyncResponse,
+}
+
+/// Warp Sync Error
+///
+/// See [`Client::warp_sync`](super::http::Client::warp_sync) for more information.
+#[derive(Debug)]
+pub enum WarpSyncError {
+    /// Transport-Level Error
+    Connection(Error),
+
+    /// Signer-Reported Sync Error
+    Sync(SyncError),
+
+    /// Untrusted Starting Checkpoint
+    ///
+    /// The signer's response did not start from the [`Checkpoint`] that was requested, so it was
+    /// rejected instead of being adopted.
+    UntrustedCheckpoint,
+}
+
+impl Display for WarpSyncError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Connection(err) => write!(f, "warp sync transport error: {err}"),
+            Self::Sync(err) => write!(f, "warp sync was rejected by the signer: {err:?}"),
+            Self::UntrustedCheckpoint => write!(
+                f,
+                "signer's warp sync response did not start from the trusted checkpoint"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for WarpSyncError {}
+
+impl From<Error> for WarpSyncError {
+    #[inline]
+    fn from(err: Error) -> Self {
+        Self::Connection(err)
+    }
+}
+
+impl From<SyncError> for WarpSyncError {
+    #[inline]
+    fn from(err: SyncError) -> Self {
+        Self::Sync(err)
+    }
+}
+
+/// Independent Warp Sync Response Verifier
+///
+/// [`WarpSyncResponse::base_checkpoint`] is reported by the signer itself, so comparing it against
+/// the trusted checkpoint proves nothing against a dishonest signer: it can echo back whatever was
+/// requested while forging [`WarpSyncResponse::response`]'s accumulator state and witnesses. A
+/// [`WarpSyncVerifier`] supplies the independent recomputation (e.g. checking the returned
+/// witnesses against an externally-sourced accumulator root for `trusted_checkpoint`) that the
+/// signer cannot fake, and is required by [`Client::warp_sync`](super::http::Client::warp_sync) on
+/// every call.
+pub trait WarpSyncVerifier {
+    /// Returns `true` if `response` is consistent with having started from
+    /// `trusted_checkpoint`, by some means independent of what the signer reported about itself.
+    fn verify(&self, trusted_checkpoint: &Checkpoint, response: &SyncResponse) -> bool;
+}
+
+/// Checks that `response` actually started from `trusted_checkpoint`, both by comparing the
+/// signer-reported [`WarpSyncResponse::base_checkpoint`] and, more importantly, by asking
+/// `verifier` to independently confirm [`WarpSyncResponse::response`] against
+/// `trusted_checkpoint`. Returns the verified [`SyncResponse`] or
+/// [`WarpSyncError::UntrustedCheckpoint`] if either check fails.
+#[inline]
+pub fn verify_base_checkpoint<V>(
+    trusted_checkpoint: &Checkpoint,
+    response: WarpSyncResponse,
+    verifier: V,
+) -> Result<SyncResponse, WarpSyncError>
+where
+    V: WarpSyncVerifier,
+{
+    if &response.base_checkpoint != trusted_checkpoint {
+        return Err(WarpSyncError::UntrustedCheckpoint);
+    }
+    if !verifier.verify(trusted_checkpoint, &response.response) {
+        return Err(WarpSyncError::UntrustedCheckpoint);
+    }
+    Ok(response.response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct AlwaysAccept;
+
+    impl WarpSyncVerifier for AlwaysAccept {
+        fn verify(&self, _: &Checkpoint, _: &SyncResponse) -> bool {
+            true
+        }
+    }
+
+    struct AlwaysReject;
+
+    impl WarpSyncVerifier for AlwaysReject {
+        fn verify(&self, _: &Checkpoint, _: &SyncResponse) -> bool {
+            false
+        }
+    }
+
+    fn response_matching(trusted: &Checkpoint) -> WarpSyncResponse {
+        WarpSyncResponse {
+            base_checkpoint: trusted.clone(),
+            response: SyncResponse::default(),
+        }
+    }
+
+    #[test]
+    fn accepts_when_base_checkpoint_and_verifier_both_agree() {
+        let trusted = Checkpoint::default();
+        let response = response_matching(&trusted);
+        assert!(verify_base_checkpoint(&trusted, response, AlwaysAccept).is_ok());
+    }
+
+    #[test]
+    fn rejects_when_verifier_refuses_even_though_base_checkpoint_matches() {
+        // A dishonest signer can trivially echo `trusted` back as `base_checkpoint` while forging
+        // the rest of `response`; the verifier, not the echoed field, is what has to catch that.
+        let trusted = Checkpoint::default();
+        let response = response_matching(&trusted);
+        assert!(matches!(
+            verify_base_checkpoint(&trusted, response, AlwaysReject),
+            Err(WarpSyncError::UntrustedCheckpoint)
+        ));
+    }
+}
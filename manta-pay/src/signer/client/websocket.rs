@@ -0,0 +1,243 @@
+// Copyright 2019-2022 Manta Network.
+// This file is part of manta-rs.
+//
+// manta-rs is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+//
+// manta-rs is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+//
+// You should have received a copy of the GNU General Public License
+// along with manta-rs.  If not, see <http://www.gnu.org/licenses/>.
+
+//! WebSocket Signer Transport
+//!
+//! Keeps a single multiplexed WebSocket connection open for the lifetime of the wallet instead
+//! of opening a new TCP/TLS connection per [`Transport::send`] call. Every request is tagged
+//! with a request id so that responses can be routed back to the caller awaiting them out of
+//! order. Each request receives exactly one response frame: a frame whose id has no caller
+//! waiting on it, including any later frame for an already-answered id, is discarded, so
+//! server-pushed `sync` continuations are not delivered.
+
+use crate::signer::client::transport::{Transport, TransportError};
+use alloc::{boxed::Box, vec::Vec};
+use futures::{SinkExt, StreamExt};
+use manta_util::future::LocalBoxFutureResult;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+use tokio::{net::TcpStream, sync::oneshot};
+use tokio_tungstenite::{
+    connect_async,
+    tungstenite::{Error as WsError, Message as WsMessage},
+    MaybeTlsStream, WebSocketStream,
+};
+
+/// A Single Multiplexed Frame
+///
+/// `id` correlates a request with its response; `command` is only meaningful on requests.
+struct Frame {
+    /// Request/Response Correlation Id
+    id: u64,
+
+    /// Command Name, Empty on Responses
+    command: Box<str>,
+
+    /// Encoded Request or Response Body
+    body: Vec<u8>,
+}
+
+impl Frame {
+    /// Encodes `self` into a single WebSocket binary frame.
+    fn encode(&self) -> Vec<u8> {
+        let command = self.command.as_bytes();
+        let mut out = Vec::with_capacity(8 + 2 + command.len() + self.body.len());
+        out.extend_from_slice(&self.id.to_le_bytes());
+        out.extend_from_slice(&(command.len() as u16).to_le_bytes());
+        out.extend_from_slice(command);
+        out.extend_from_slice(&self.body);
+        out
+    }
+
+    /// Decodes `self` from a single WebSocket binary frame.
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        if bytes.len() < 10 {
+            return None;
+        }
+        let id = u64::from_le_bytes(bytes[0..8].try_into().ok()?);
+        let command_len = u16::from_le_bytes(bytes[8..10].try_into().ok()?) as usize;
+        let command = bytes.get(10..10 + command_len)?;
+        let body = bytes.get(10 + command_len..)?;
+        Some(Self {
+            id,
+            command: core::str::from_utf8(command).ok()?.into(),
+            body: body.to_vec(),
+        })
+    }
+}
+
+/// Pending Response Table
+type PendingTable = Mutex<HashMap<u64, oneshot::Sender<Vec<u8>>>>;
+
+/// WebSocket Signer Transport
+pub struct WebSocketTransport {
+    /// Outgoing Frame Sink
+    sink: tokio::sync::Mutex<
+        futures::stream::SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, WsMessage>,
+    >,
+
+    /// Responses Awaiting Delivery, Keyed by Request Id
+    pending: Arc<PendingTable>,
+
+    /// Next Request Id to Assign
+    next_id: AtomicU64,
+}
+
+impl WebSocketTransport {
+    /// Connects to `server_url`, keeping the resulting connection open for the lifetime of the
+    /// returned [`WebSocketTransport`].
+    #[inline]
+    pub async fn connect(server_url: &str) -> Result<Self, WsError> {
+        let (stream, _) = connect_async(server_url).await?;
+        let (sink, mut stream) = stream.split();
+        let pending: Arc<PendingTable> = Arc::new(Mutex::new(HashMap::new()));
+        let reader_pending = pending.clone();
+        tokio::spawn(async move {
+            while let Some(Ok(message)) = stream.next().await {
+                if let WsMessage::Binary(bytes) = message {
+                    if let Some(frame) = Frame::decode(&bytes) {
+                        if let Some(sender) = reader_pending
+                            .lock()
+                            .expect("Pending response table lock was poisoned.")
+                            .remove(&frame.id)
+                        {
+                            let _ = sender.send(frame.body);
+                        }
+                    }
+                }
+            }
+            // The connection is gone: drop every still-pending sender so that callers awaiting
+            // a response get a closed-channel error instead of hanging forever.
+            reader_pending
+                .lock()
+                .expect("Pending response table lock was poisoned.")
+                .clear();
+        });
+        Ok(Self {
+            sink: tokio::sync::Mutex::new(sink),
+            pending,
+            next_id: AtomicU64::new(0),
+        })
+    }
+}
+
+impl Transport for WebSocketTransport {
+    #[inline]
+    fn send(&self, command: &str, body: Vec<u8>) -> LocalBoxFutureResult<Vec<u8>, TransportError> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let frame = Frame {
+            id,
+            command: command.into(),
+            body,
+        }
+        .encode();
+        let (sender, receiver) = oneshot::channel();
+        self.pending
+            .lock()
+            .expect("Pending response table lock was poisoned.")
+            .insert(id, sender);
+        Box::pin(async move {
+            if let Err(err) = self
+                .sink
+                .lock()
+                .await
+                .send(WsMessage::Binary(frame))
+                .await
+            {
+                // The request never made it onto the wire, so no response will ever arrive for
+                // `id`; remove it here instead of leaking the slot until the reader task clears
+                // the whole table on disconnect.
+                self.pending
+                    .lock()
+                    .expect("Pending response table lock was poisoned.")
+                    .remove(&id);
+                return Err(TransportError::WebSocket(err));
+            }
+            receiver.await.map_err(|_| {
+                TransportError::WebSocket(WsError::ConnectionClosed)
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frame_round_trips_through_encode_decode() {
+        let frame = Frame {
+            id: 42,
+            command: "sync".into(),
+            body: Vec::from([1, 2, 3, 4]),
+        };
+        let decoded = Frame::decode(&frame.encode()).unwrap();
+        assert_eq!(decoded.id, 42);
+        assert_eq!(&*decoded.command, "sync");
+        assert_eq!(decoded.body, Vec::from([1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn frame_round_trips_with_empty_command_and_body() {
+        let frame = Frame {
+            id: 0,
+            command: "".into(),
+            body: Vec::new(),
+        };
+        let decoded = Frame::decode(&frame.encode()).unwrap();
+        assert_eq!(decoded.id, 0);
+        assert_eq!(&*decoded.command, "");
+        assert!(decoded.body.is_empty());
+    }
+
+    #[test]
+    fn decode_rejects_frames_too_short_to_contain_a_header() {
+        assert!(Frame::decode(&[0; 9]).is_none());
+        assert!(Frame::decode(&[]).is_none());
+    }
+
+    #[test]
+    fn removing_a_pending_entry_on_send_failure_fails_its_receiver() {
+        let pending: PendingTable = Mutex::new(HashMap::new());
+        let (sender, receiver) = oneshot::channel::<Vec<u8>>();
+        pending.lock().unwrap().insert(7, sender);
+
+        // Simulates Transport::send's cleanup after the sink write fails.
+        pending.lock().unwrap().remove(&7);
+
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn clearing_the_pending_table_fails_every_outstanding_receiver() {
+        let pending: Arc<PendingTable> = Arc::new(Mutex::new(HashMap::new()));
+        let (sender_a, receiver_a) = oneshot::channel::<Vec<u8>>();
+        let (sender_b, receiver_b) = oneshot::channel::<Vec<u8>>();
+        pending.lock().unwrap().insert(0, sender_a);
+        pending.lock().unwrap().insert(1, sender_b);
+
+        // Simulates the reader task's cleanup once its connection drops.
+        pending.lock().unwrap().clear();
+
+        assert!(receiver_a.await.is_err());
+        assert!(receiver_b.await.is_err());
+    }
+}